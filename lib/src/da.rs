@@ -0,0 +1,208 @@
+//! Reed-Solomon erasure-coded data-availability commitments.
+//!
+//! A payload is erasure-coded into `n` shards — `data = n - 2f` data shards and
+//! `2f` parity shards — and a Merkle tree is built over the shard hashes. This is
+//! the commitment scheme used in HoneyBadger-style reliable broadcast (hbbft),
+//! which pairs `reed-solomon-erasure` with a Merkle tree of the shards: any
+//! `data`-sized subset reconstructs the payload, and a single shard can be proven
+//! to belong to the agreed root without revealing the whole payload.
+
+use reed_solomon_erasure::galois_8::ReedSolomon;
+
+use crate::{compute_leaf_hash, hash_pair, HashAlgorithm};
+
+/// Errors returned by the data-availability encoder.
+#[derive(Debug)]
+pub enum DaError {
+    /// `n` is too small to tolerate `f` faults (requires `n >= 2f + 1`).
+    TooManyFaults,
+    /// The underlying Reed-Solomon codec failed.
+    ReedSolomon(reed_solomon_erasure::Error),
+}
+
+impl From<reed_solomon_erasure::Error> for DaError {
+    fn from(err: reed_solomon_erasure::Error) -> Self {
+        DaError::ReedSolomon(err)
+    }
+}
+
+/// The number of data shards for an `(n, f)` configuration: `n - 2f`.
+pub fn data_shard_count(n: usize, f: usize) -> usize {
+    n - 2 * f
+}
+
+/// Erasure-code `data` into `n` equal-length shards (`n - 2f` data shards
+/// followed by `2f` parity shards), padding the final data shard with zeros.
+pub fn encode(data: &[u8], n: usize, f: usize) -> Result<Vec<Vec<u8>>, DaError> {
+    if n < 2 * f + 1 {
+        return Err(DaError::TooManyFaults);
+    }
+
+    let data_shards = data_shard_count(n, f);
+    let parity_shards = 2 * f;
+    let shard_len = data.len().div_ceil(data_shards).max(1);
+
+    // Split the payload across the data shards, zero-padding the tail.
+    let mut shards: Vec<Vec<u8>> = Vec::with_capacity(n);
+    for i in 0..data_shards {
+        let start = (i * shard_len).min(data.len());
+        let end = ((i + 1) * shard_len).min(data.len());
+        let mut shard = vec![0u8; shard_len];
+        shard[..end - start].copy_from_slice(&data[start..end]);
+        shards.push(shard);
+    }
+    for _ in 0..parity_shards {
+        shards.push(vec![0u8; shard_len]);
+    }
+
+    if parity_shards > 0 {
+        ReedSolomon::new(data_shards, parity_shards)?.encode(&mut shards)?;
+    }
+
+    Ok(shards)
+}
+
+/// Reconstruct the original payload from a set of received shards, where missing
+/// shards are `None`. Up to `2f` shards may be absent. The returned bytes are the
+/// concatenated data shards and may carry the zero padding added by [`encode`].
+pub fn reconstruct(received: &mut [Option<Vec<u8>>], n: usize, f: usize) -> Result<Vec<u8>, DaError> {
+    if n < 2 * f + 1 {
+        return Err(DaError::TooManyFaults);
+    }
+
+    let data_shards = data_shard_count(n, f);
+    let parity_shards = 2 * f;
+
+    if parity_shards > 0 {
+        ReedSolomon::new(data_shards, parity_shards)?.reconstruct(received)?;
+    }
+
+    let mut data = Vec::new();
+    for shard in received.iter().take(data_shards) {
+        match shard {
+            Some(bytes) => data.extend_from_slice(bytes),
+            None => return Err(DaError::ReedSolomon(reed_solomon_erasure::Error::TooFewShardsPresent)),
+        }
+    }
+    Ok(data)
+}
+
+/// Build the levels of a Merkle tree over `leaves`, padding the leaf count up to
+/// the next power of two with zero-hash leaves so the tree is perfectly balanced.
+/// Level `0` holds the (padded) leaves and the final level holds the root.
+fn build_levels(leaves: &[[u8; 32]], alg: HashAlgorithm) -> Vec<Vec<[u8; 32]>> {
+    let mut level = leaves.to_vec();
+    level.resize(leaves.len().next_power_of_two().max(1), [0u8; 32]);
+
+    let mut levels = vec![level.clone()];
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hash_pair(pair[0], pair[1], alg))
+            .collect();
+        levels.push(level.clone());
+    }
+    levels
+}
+
+/// The Merkle root over a set of shard-hash leaves.
+pub fn merkle_root(leaves: &[[u8; 32]], alg: HashAlgorithm) -> [u8; 32] {
+    build_levels(leaves, alg)
+        .last()
+        .map(|top| top[0])
+        .unwrap_or([0u8; 32])
+}
+
+/// Produce the sibling path and left/right flags proving that the leaf at
+/// `index` is a member of the tree, suitable for [`crate::verify_merkle_path`].
+pub fn merkle_proof(
+    leaves: &[[u8; 32]],
+    index: usize,
+    alg: HashAlgorithm,
+) -> (Vec<[u8; 32]>, Vec<bool>) {
+    let levels = build_levels(leaves, alg);
+    let mut proof = Vec::new();
+    let mut indices = Vec::new();
+    let mut idx = index;
+
+    for level in &levels[..levels.len() - 1] {
+        proof.push(level[idx ^ 1]);
+        // A right child (odd index) is hashed as (sibling, current).
+        indices.push(idx & 1 == 1);
+        idx /= 2;
+    }
+
+    (proof, indices)
+}
+
+/// Erasure-code `data` and commit to the resulting shards with a Merkle tree,
+/// returning the per-shard leaf hashes together with the shared root.
+pub fn encode_shards(
+    data: &[u8],
+    n: usize,
+    f: usize,
+    alg: HashAlgorithm,
+) -> Result<(Vec<[u8; 32]>, [u8; 32]), DaError> {
+    let shards = encode(data, n, f)?;
+    let leaves: Vec<[u8; 32]> = shards
+        .iter()
+        .map(|shard| compute_leaf_hash(shard, alg))
+        .collect();
+    let root = merkle_root(&leaves, alg);
+    Ok((leaves, root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verify_merkle_path;
+
+    const ALG: HashAlgorithm = HashAlgorithm::Keccak256;
+
+    #[test]
+    fn test_encode_drop_reconstruct_stable_root() {
+        // n = 6, f = 1 → 4 data shards, 2 parity shards, tolerating 2 losses.
+        let (n, f) = (6, 1);
+        let data = b"the quick brown fox jumps over the lazy dog".to_vec();
+
+        let shards = encode(&data, n, f).expect("encode");
+        assert_eq!(shards.len(), n);
+
+        let leaves: Vec<[u8; 32]> = shards.iter().map(|s| compute_leaf_hash(s, ALG)).collect();
+        let root = merkle_root(&leaves, ALG);
+
+        // Drop f shards and reconstruct.
+        let mut received: Vec<Option<Vec<u8>>> = shards.iter().cloned().map(Some).collect();
+        received[0] = None;
+        let recovered = reconstruct(&mut received, n, f).expect("reconstruct");
+
+        // The recovered data shards match the original payload (with tail padding).
+        assert!(recovered.starts_with(&data));
+
+        // The Merkle root over the reconstructed shards is unchanged.
+        let restored: Vec<[u8; 32]> = received
+            .iter()
+            .map(|s| compute_leaf_hash(s.as_ref().unwrap(), ALG))
+            .collect();
+        assert_eq!(merkle_root(&restored, ALG), root);
+    }
+
+    #[test]
+    fn test_shard_membership_proof() {
+        let (n, f) = (6, 1);
+        let data = b"data availability payload".to_vec();
+
+        let (leaves, root) = encode_shards(&data, n, f, ALG).expect("encode_shards");
+
+        // Every shard proves membership against the committed root.
+        for (index, &leaf) in leaves.iter().enumerate() {
+            let (proof, indices) = merkle_proof(&leaves, index, ALG);
+            assert!(verify_merkle_path(leaf, root, &proof, &indices, ALG));
+        }
+
+        // A shard hash that is not in the tree is rejected.
+        let bogus = compute_leaf_hash(b"not a shard", ALG);
+        let (proof, indices) = merkle_proof(&leaves, 0, ALG);
+        assert!(!verify_merkle_path(bogus, root, &proof, &indices, ALG));
+    }
+}
@@ -1,22 +1,93 @@
 use alloy_sol_types::sol;
+use serde::{Deserialize, Serialize};
+
+pub mod da;
 
 sol! {
     /// The public values encoded as a struct that can be easily deserialized inside Solidity.
     struct PublicValuesStruct {
         bytes32 leaf;
         bytes32 root;
+        bytes32 key;
+        bytes32 old_root;
+        bytes32 new_root;
+        uint32 leaf_count;
+        uint8 hash_algorithm;
+        uint64 index;
+        uint32 depth;
+        uint32 shard_index;
+        bytes32 shard_hash;
+        bool is_member;
         bool is_valid;
     }
 }
 
-/// Compute the Keccak256 hash of two 32-byte values concatenated together.
-pub fn hash_pair(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
-    use sha3::{Digest, Keccak256};
-    let mut hasher = Keccak256::new();
-    hasher.update(&left);
-    hasher.update(&right);
-    let result = hasher.finalize();
-    result.into()
+/// The hash function used to build and verify the tree.
+///
+/// On-chain verifiers and off-chain commitments often disagree (Keccak256 for
+/// the EVM, SHA-256 elsewhere), so the algorithm is selectable rather than
+/// hardcoded, following the `HashType` design of the DCRM proof tool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HashAlgorithm {
+    /// SHA-256 (FIPS 180-4).
+    Sha256,
+    /// Keccak256 (the EVM / pre-standard SHA-3 variant).
+    Keccak256,
+}
+
+impl HashAlgorithm {
+    /// Hash an arbitrary byte slice into a 32-byte digest.
+    pub fn hash(&self, data: &[u8]) -> [u8; 32] {
+        use sha3::Digest;
+        match self {
+            HashAlgorithm::Sha256 => {
+                let mut hasher = sha2::Sha256::new();
+                hasher.update(data);
+                hasher.finalize().into()
+            }
+            HashAlgorithm::Keccak256 => {
+                let mut hasher = sha3::Keccak256::new();
+                hasher.update(data);
+                hasher.finalize().into()
+            }
+        }
+    }
+
+    /// Encode the algorithm as the single input byte read by the guest and
+    /// committed in [`PublicValuesStruct`].
+    pub fn as_byte(&self) -> u8 {
+        match self {
+            HashAlgorithm::Sha256 => 0,
+            HashAlgorithm::Keccak256 => 1,
+        }
+    }
+
+    /// Decode the algorithm from its committed input byte, defaulting to
+    /// Keccak256 for any unrecognised value.
+    pub fn from_byte(byte: u8) -> Self {
+        match byte {
+            0 => HashAlgorithm::Sha256,
+            _ => HashAlgorithm::Keccak256,
+        }
+    }
+}
+
+/// Domain-separation prefix for leaf hashes (RFC 6962 `0x00`).
+pub const LEAF_DOMAIN: u8 = 0x00;
+/// Domain-separation prefix for internal-node hashes (RFC 6962 `0x01`).
+pub const NODE_DOMAIN: u8 = 0x01;
+
+/// Hash two 32-byte values concatenated together under the given algorithm.
+///
+/// The pair is prefixed with [`NODE_DOMAIN`] so that an internal node can never
+/// be confused with a leaf (whose preimage is prefixed with [`LEAF_DOMAIN`]),
+/// matching certificate-transparency Merkle trees.
+pub fn hash_pair(left: [u8; 32], right: [u8; 32], alg: HashAlgorithm) -> [u8; 32] {
+    let mut data = [0u8; 65];
+    data[0] = NODE_DOMAIN;
+    data[1..33].copy_from_slice(&left);
+    data[33..].copy_from_slice(&right);
+    alg.hash(&data)
 }
 
 /// Verify a Merkle tree path.
@@ -34,70 +105,382 @@ pub fn verify_merkle_path(
     root: [u8; 32],
     proof: &[[u8; 32]],
     indices: &[bool],
+    alg: HashAlgorithm,
 ) -> bool {
     if proof.len() != indices.len() {
         return false;
     }
 
+    compute_merkle_root(leaf, proof, indices, alg) == root
+}
+
+/// Verify a Merkle proof where the leaf's position is given by a single `index`
+/// rather than a parallel vector of left/right flags, following the lighthouse
+/// `verify_merkle_proof(leaf, branch, depth, index, root)` shape.
+///
+/// At level `i` the orientation is derived from bit `i` of `index`: a set bit
+/// means the running hash is the right child (sibling on the left), a clear bit
+/// means it is the left child. The proof is rejected when `branch.len() != depth`.
+///
+/// # Arguments
+/// * `leaf` - The leaf node hash
+/// * `branch` - The sibling hashes from leaf to root, one per level
+/// * `depth` - The tree depth; must equal `branch.len()`
+/// * `index` - The leaf's position, whose bits encode left/right at each level
+/// * `root` - The expected Merkle root
+/// * `alg` - The hash algorithm
+pub fn verify_merkle_proof_indexed(
+    leaf: [u8; 32],
+    branch: &[[u8; 32]],
+    depth: usize,
+    index: u64,
+    root: [u8; 32],
+    alg: HashAlgorithm,
+) -> bool {
+    if branch.len() != depth {
+        return false;
+    }
+
+    let mut node = leaf;
+    for (i, &sibling) in branch.iter().enumerate() {
+        if (index >> i) & 1 == 1 {
+            // The running hash is the right child at this level.
+            node = hash_pair(sibling, node, alg);
+        } else {
+            // The running hash is the left child at this level.
+            node = hash_pair(node, sibling, alg);
+        }
+    }
+
+    node == root
+}
+
+/// Recompute the Merkle root reached by folding `leaf` upward along `proof`.
+///
+/// `indices[i]` is `true` when the running hash is the right child at level `i`
+/// (so the sibling goes on the left) and `false` when it is the left child.
+/// The caller must ensure `proof.len() == indices.len()`.
+pub fn compute_merkle_root(
+    leaf: [u8; 32],
+    proof: &[[u8; 32]],
+    indices: &[bool],
+    alg: HashAlgorithm,
+) -> [u8; 32] {
     let mut current_hash = leaf;
 
     for (i, &sibling) in proof.iter().enumerate() {
         if indices[i] {
             // Current hash goes on the right, sibling on the left
-            current_hash = hash_pair(sibling, current_hash);
+            current_hash = hash_pair(sibling, current_hash, alg);
         } else {
             // Current hash goes on the left, sibling on the right
-            current_hash = hash_pair(current_hash, sibling);
+            current_hash = hash_pair(current_hash, sibling, alg);
         }
     }
 
-    current_hash == root
+    current_hash
 }
 
 /// Compute the hash of arbitrary data to create a leaf node.
-pub fn compute_leaf_hash(data: &[u8]) -> [u8; 32] {
-    use sha3::{Digest, Keccak256};
-    let mut hasher = Keccak256::new();
-    hasher.update(data);
-    let result = hasher.finalize();
-    result.into()
+///
+/// The data is prefixed with [`LEAF_DOMAIN`] so a leaf can never collide with an
+/// internal node's preimage (which is prefixed with [`NODE_DOMAIN`]).
+pub fn compute_leaf_hash(data: &[u8], alg: HashAlgorithm) -> [u8; 32] {
+    let mut prefixed = Vec::with_capacity(1 + data.len());
+    prefixed.push(LEAF_DOMAIN);
+    prefixed.extend_from_slice(data);
+    alg.hash(&prefixed)
+}
+
+/// Compute the leaf hash binding a key to its value, `hash(len(key) || key || value)`,
+/// where `len(key)` is the key length as 8 big-endian bytes.
+///
+/// Non-membership proofs rely on the leaf committing to its key, so neighbours
+/// cannot be replayed for a different key than the one they actually store; the
+/// length prefix keeps the key/value split unambiguous.
+pub fn compute_kv_leaf_hash(key: &[u8], value: &[u8], alg: HashAlgorithm) -> [u8; 32] {
+    let mut data = Vec::with_capacity(8 + key.len() + value.len());
+    data.extend_from_slice(&(key.len() as u64).to_be_bytes());
+    data.extend_from_slice(key);
+    data.extend_from_slice(value);
+    compute_leaf_hash(&data, alg)
+}
+
+/// A reserved leaf index for a non-existent neighbour, i.e. when the queried
+/// key is smaller than every stored key (no `left` neighbour) or larger than
+/// every stored key (no `right` neighbour). [`verify_merkle_non_membership`]
+/// does not read it: a missing neighbour is passed as `None`.
+pub const BOUNDARY_SENTINEL: u32 = u32::MAX;
+
+/// A membership proof for one of the two neighbours that bracket a queried key
+/// in a key-sorted Merkle tree.
+///
+/// The tree keeps its leaves sorted by `key`; a non-membership proof for `k`
+/// supplies the largest leaf with `key < k` and the smallest leaf with
+/// `key > k`, each carrying its own path to the shared root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NeighborProof {
+    /// The key stored at this leaf.
+    pub key: Vec<u8>,
+    /// The value stored at this leaf.
+    pub value: Vec<u8>,
+    /// The leaf index within the sorted tree.
+    pub index: u32,
+    /// Sibling hashes on the path from the leaf to the root.
+    pub proof: Vec<[u8; 32]>,
+    /// Left/right orientation flags matching `proof`.
+    pub indices: Vec<bool>,
+}
+
+impl NeighborProof {
+    /// Verify this neighbour's membership against `root` under `alg`.
+    ///
+    /// The numeric `index` is bound to the proof: bit `i` of `index` must match
+    /// `indices[i]` (set bit ⇔ the leaf is the right child at level `i`). Without
+    /// this binding the adjacency and boundary checks in
+    /// [`verify_merkle_non_membership`] would test an attacker-chosen integer that
+    /// the folded proof never constrains, letting a prover pass honest membership
+    /// proofs for two non-adjacent leaves while lying about their positions.
+    fn verify(&self, root: [u8; 32], alg: HashAlgorithm) -> bool {
+        if self.proof.len() != self.indices.len() {
+            return false;
+        }
+        for (i, &bit) in self.indices.iter().enumerate() {
+            if bit != ((self.index as u64 >> i) & 1 == 1) {
+                return false;
+            }
+        }
+
+        let leaf = compute_kv_leaf_hash(&self.key, &self.value, alg);
+        verify_merkle_path(leaf, root, &self.proof, &self.indices, alg)
+    }
+}
+
+/// Verify that `key` is *absent* from a key-sorted Merkle tree committed to by
+/// `root`, in the style of the ICS23 non-existence proofs used in IBC.
+///
+/// The prover supplies membership proofs for the queried key's immediate
+/// neighbours: `left` is the largest leaf whose key is `< key`, and `right` is
+/// the smallest leaf whose key is `> key`. The verifier checks that
+/// 1. each supplied neighbour proof validates against `root`,
+/// 2. the neighbours straddle the key (`left.key < key < right.key`), and
+/// 3. the neighbours are adjacent — their indices differ by one, or the missing
+///    side is anchored to a boundary (`key` below the smallest or above the
+///    largest key).
+///
+/// Exactly one side may be omitted to signal that `key` falls before the first
+/// leaf (`left == None`) or after the last leaf (`right == None`). Each boundary
+/// must be pinned to an extreme leaf: a right-only proof requires `right.index`
+/// to be the smallest index (`0`) and a left-only proof requires `left.index` to
+/// be the largest (`leaf_count - 1`). The verifier cannot otherwise tell which
+/// index is last, so `leaf_count` is committed in [`PublicValuesStruct`]; without
+/// it a prover could prove a present key absent by offering any smaller leaf as a
+/// left-only neighbour.
+pub fn verify_merkle_non_membership(
+    key: &[u8],
+    root: [u8; 32],
+    leaf_count: u32,
+    left: Option<&NeighborProof>,
+    right: Option<&NeighborProof>,
+    alg: HashAlgorithm,
+) -> bool {
+    match (left, right) {
+        (Some(left), Some(right)) => {
+            left.verify(root, alg)
+                && right.verify(root, alg)
+                && left.key.as_slice() < key
+                && key < right.key.as_slice()
+                && right.index == left.index + 1
+        }
+        // `key` is smaller than every stored key: only a right neighbour, which
+        // must be the smallest leaf (index 0).
+        (None, Some(right)) => {
+            right.verify(root, alg) && key < right.key.as_slice() && right.index == 0
+        }
+        // `key` is larger than every stored key: only a left neighbour, which must
+        // be the final leaf (`leaf_count - 1`) so no later leaf can be hidden.
+        (Some(left), None) => {
+            leaf_count > 0
+                && left.verify(root, alg)
+                && left.key.as_slice() < key
+                && left.index == leaf_count - 1
+        }
+        // A non-membership proof must supply at least one neighbour.
+        (None, None) => false,
+    }
+}
+
+/// Verify many leaves against a single `root` from one compressed proof that
+/// shares and deduplicates internal nodes, in the style of the batch openings in
+/// the tlsnotary Merkle module.
+///
+/// `leaves` is a slice of `(index, hash)` pairs; `proof` holds only the sibling
+/// hashes that are *not* themselves among the proven leaves, listed in index
+/// order. The tree is folded level by level bottom-up: at each level two nodes
+/// that are siblings of each other are paired directly (consuming both), and any
+/// remaining node pulls its sibling from `proof`. A proof element is never
+/// consumed for a node whose sibling is also being proven. The verifier succeeds
+/// when the single surviving node equals `root`.
+pub fn verify_merkle_multiproof(
+    leaves: &[(u32, [u8; 32])],
+    proof: &[[u8; 32]],
+    root: [u8; 32],
+    alg: HashAlgorithm,
+) -> bool {
+    if leaves.is_empty() {
+        return false;
+    }
+
+    // Process a level sorted by index so that sibling pairs are adjacent.
+    let mut nodes: Vec<(u32, [u8; 32])> = leaves.to_vec();
+    nodes.sort_by_key(|&(index, _)| index);
+
+    let mut proof_iter = proof.iter();
+
+    // Climb until a single node remains at the root position (index 0) and every
+    // proof element has been consumed; the left-most node of any level also has
+    // index 0, so the index alone does not mark the top.
+    while nodes.len() > 1 || nodes[0].0 != 0 || proof_iter.len() > 0 {
+        let mut next = Vec::with_capacity(nodes.len());
+        let mut i = 0;
+
+        while i < nodes.len() {
+            let (index, hash) = nodes[i];
+            let sibling_index = index ^ 1;
+
+            let parent = if i + 1 < nodes.len() && nodes[i + 1].0 == sibling_index {
+                // Both siblings are proven: pair them and consume both.
+                let (_, sibling) = nodes[i + 1];
+                i += 2;
+                if index % 2 == 0 {
+                    hash_pair(hash, sibling, alg)
+                } else {
+                    hash_pair(sibling, hash, alg)
+                }
+            } else {
+                // The sibling is supplied by the proof.
+                let sibling = match proof_iter.next() {
+                    Some(&sibling) => sibling,
+                    None => return false,
+                };
+                i += 1;
+                if index % 2 == 0 {
+                    hash_pair(hash, sibling, alg)
+                } else {
+                    hash_pair(sibling, hash, alg)
+                }
+            };
+
+            next.push((index / 2, parent));
+        }
+
+        nodes = next;
+    }
+
+    nodes[0].1 == root
+}
+
+/// A single key/value write within a batched state transition.
+///
+/// Modeled on the `TreeEntry` of zkSync's loadtest: each update carries the
+/// `key` being written, its `leaf_index`, the `old_value` currently stored and
+/// the `new_value` to store, together with the sibling path proving the leaf's
+/// current membership (which is reused to recompute the updated root).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateUpdate {
+    /// The key being written.
+    pub key: Vec<u8>,
+    /// The leaf index within the tree.
+    pub leaf_index: u32,
+    /// The value stored at the leaf before this update (the zero-byte vector for
+    /// an insertion into an empty slot).
+    pub old_value: Vec<u8>,
+    /// The value to store at the leaf.
+    pub new_value: Vec<u8>,
+    /// Sibling hashes on the path from the leaf to the current root.
+    pub sibling_path: Vec<[u8; 32]>,
+    /// Left/right orientation flags matching `sibling_path`.
+    pub indices: Vec<bool>,
+}
+
+/// Apply an ordered batch of key/value updates on top of `old_root`, returning
+/// the resulting root once every update has been folded in.
+///
+/// For each update the verifier (1) checks that `hash(key || old_value)` is a
+/// member of the tree under the running root via the supplied sibling path, then
+/// (2) recomputes the root after replacing that leaf with `hash(key || new_value)`
+/// using the same path, and (3) feeds the recomputed root forward as the root for
+/// the next update. This lets overlapping sibling paths be reused across writes
+/// within the batch. Returns `None` if any membership check fails.
+pub fn apply_state_updates(
+    old_root: [u8; 32],
+    updates: &[StateUpdate],
+    alg: HashAlgorithm,
+) -> Option<[u8; 32]> {
+    let mut current_root = old_root;
+
+    for update in updates {
+        if update.sibling_path.len() != update.indices.len() {
+            return None;
+        }
+
+        // (1) The old leaf must be a member of the tree under the running root.
+        let old_leaf = compute_kv_leaf_hash(&update.key, &update.old_value, alg);
+        if !verify_merkle_path(old_leaf, current_root, &update.sibling_path, &update.indices, alg) {
+            return None;
+        }
+
+        // (2) Replace the leaf and (3) carry the updated root forward.
+        let new_leaf = compute_kv_leaf_hash(&update.key, &update.new_value, alg);
+        current_root =
+            compute_merkle_root(new_leaf, &update.sibling_path, &update.indices, alg);
+    }
+
+    Some(current_root)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// The algorithm used by the bulk of the tests; the original tree hashed
+    /// everything with Keccak256.
+    const ALG: HashAlgorithm = HashAlgorithm::Keccak256;
+
     #[test]
     fn test_hash_pair() {
         let left = [1u8; 32];
         let right = [2u8; 32];
-        let result = hash_pair(left, right);
+        let result = hash_pair(left, right, ALG);
 
         // The result should be deterministic
-        let result2 = hash_pair(left, right);
+        let result2 = hash_pair(left, right, ALG);
         assert_eq!(result, result2);
 
         // Different order should give different result
-        let result3 = hash_pair(right, left);
+        let result3 = hash_pair(right, left, ALG);
         assert_ne!(result, result3);
+
+        // Different algorithms give different digests for the same input.
+        assert_ne!(result, hash_pair(left, right, HashAlgorithm::Sha256));
     }
 
     #[test]
     fn test_simple_merkle_path() {
         // Create a simple 2-level tree
-        let leaf1 = compute_leaf_hash(b"data1");
-        let leaf2 = compute_leaf_hash(b"data2");
-        let root = hash_pair(leaf1, leaf2);
+        let leaf1 = compute_leaf_hash(b"data1", ALG);
+        let leaf2 = compute_leaf_hash(b"data2", ALG);
+        let root = hash_pair(leaf1, leaf2, ALG);
 
         // Verify path for leaf1 (left child)
         let proof = vec![leaf2];
         let indices = vec![false]; // leaf1 is left child, so leaf2 goes on right
-        assert!(verify_merkle_path(leaf1, root, &proof, &indices));
+        assert!(verify_merkle_path(leaf1, root, &proof, &indices, ALG));
 
         // Verify path for leaf2 (right child)
         let proof = vec![leaf1];
         let indices = vec![true]; // leaf2 is right child, so leaf1 goes on left
-        assert!(verify_merkle_path(leaf2, root, &proof, &indices));
+        assert!(verify_merkle_path(leaf2, root, &proof, &indices, ALG));
 
         // Invalid proof should fail
         let wrong_proof = vec![leaf1];
@@ -106,30 +489,332 @@ mod tests {
             leaf1,
             root,
             &wrong_proof,
-            &wrong_indices
+            &wrong_indices,
+            ALG
+        ));
+
+        // The same path under a different algorithm must not validate.
+        assert!(!verify_merkle_path(
+            leaf1,
+            root,
+            &vec![leaf2],
+            &vec![false],
+            HashAlgorithm::Sha256
         ));
     }
 
     #[test]
     fn test_three_level_merkle_tree() {
         // Create a 3-level tree with 4 leaves
-        let leaf1 = compute_leaf_hash(b"data1");
-        let leaf2 = compute_leaf_hash(b"data2");
-        let leaf3 = compute_leaf_hash(b"data3");
-        let leaf4 = compute_leaf_hash(b"data4");
+        let leaf1 = compute_leaf_hash(b"data1", ALG);
+        let leaf2 = compute_leaf_hash(b"data2", ALG);
+        let leaf3 = compute_leaf_hash(b"data3", ALG);
+        let leaf4 = compute_leaf_hash(b"data4", ALG);
 
-        let node1 = hash_pair(leaf1, leaf2);
-        let node2 = hash_pair(leaf3, leaf4);
-        let root = hash_pair(node1, node2);
+        let node1 = hash_pair(leaf1, leaf2, ALG);
+        let node2 = hash_pair(leaf3, leaf4, ALG);
+        let root = hash_pair(node1, node2, ALG);
 
         // Verify path for leaf1 (left-left position)
         let proof = vec![leaf2, node2];
         let indices = vec![false, false]; // both times current goes left
-        assert!(verify_merkle_path(leaf1, root, &proof, &indices));
+        assert!(verify_merkle_path(leaf1, root, &proof, &indices, ALG));
 
         // Verify path for leaf4 (right-right position)
         let proof = vec![leaf3, node1];
         let indices = vec![true, true]; // both times current goes right
-        assert!(verify_merkle_path(leaf4, root, &proof, &indices));
+        assert!(verify_merkle_path(leaf4, root, &proof, &indices, ALG));
+    }
+
+    #[test]
+    fn test_indexed_merkle_proof() {
+        // Same 4-leaf tree, verified via index/depth instead of a bool vector.
+        let leaf1 = compute_leaf_hash(b"data1", ALG);
+        let leaf2 = compute_leaf_hash(b"data2", ALG);
+        let leaf3 = compute_leaf_hash(b"data3", ALG);
+        let leaf4 = compute_leaf_hash(b"data4", ALG);
+
+        let node1 = hash_pair(leaf1, leaf2, ALG);
+        let node2 = hash_pair(leaf3, leaf4, ALG);
+        let root = hash_pair(node1, node2, ALG);
+
+        // Leaf1 is at index 0 (bits 0b00): left child at both levels.
+        assert!(verify_merkle_proof_indexed(leaf1, &[leaf2, node2], 2, 0, root, ALG));
+
+        // Leaf4 is at index 3 (bits 0b11): right child at both levels.
+        assert!(verify_merkle_proof_indexed(leaf4, &[leaf3, node1], 2, 3, root, ALG));
+
+        // Leaf3 is at index 2 (bits 0b10): left at level 0, right at level 1.
+        assert!(verify_merkle_proof_indexed(leaf3, &[leaf4, node1], 2, 2, root, ALG));
+
+        // A branch whose length disagrees with `depth` is rejected outright.
+        assert!(!verify_merkle_proof_indexed(leaf1, &[leaf2, node2], 3, 0, root, ALG));
+
+        // The wrong index orientation fails.
+        assert!(!verify_merkle_proof_indexed(leaf1, &[leaf2, node2], 2, 1, root, ALG));
+    }
+
+    #[test]
+    fn test_leaf_and_node_domain_separation() {
+        // A two-node pair must not collide with a leaf over the same 64 bytes.
+        let a = [7u8; 32];
+        let b = [9u8; 32];
+        let mut concat = Vec::new();
+        concat.extend_from_slice(&a);
+        concat.extend_from_slice(&b);
+        assert_ne!(hash_pair(a, b, ALG), compute_leaf_hash(&concat, ALG));
+    }
+
+    /// Build a 4-leaf tree over keys sorted ascending and return the root along
+    /// with a neighbour proof for each leaf.
+    fn sorted_tree() -> ([u8; 32], Vec<NeighborProof>) {
+        let keys: [&[u8]; 4] = [b"a", b"c", b"e", b"g"];
+        let values: [&[u8]; 4] = [b"1", b"2", b"3", b"4"];
+        let leaves: Vec<[u8; 32]> = keys
+            .iter()
+            .zip(values.iter())
+            .map(|(k, v)| compute_kv_leaf_hash(k, v, ALG))
+            .collect();
+
+        let node1 = hash_pair(leaves[0], leaves[1], ALG);
+        let node2 = hash_pair(leaves[2], leaves[3], ALG);
+        let root = hash_pair(node1, node2, ALG);
+
+        let neighbours = vec![
+            NeighborProof {
+                key: keys[0].to_vec(),
+                value: values[0].to_vec(),
+                index: 0,
+                proof: vec![leaves[1], node2],
+                indices: vec![false, false],
+            },
+            NeighborProof {
+                key: keys[1].to_vec(),
+                value: values[1].to_vec(),
+                index: 1,
+                proof: vec![leaves[0], node2],
+                indices: vec![true, false],
+            },
+            NeighborProof {
+                key: keys[2].to_vec(),
+                value: values[2].to_vec(),
+                index: 2,
+                proof: vec![leaves[3], node1],
+                indices: vec![false, true],
+            },
+            NeighborProof {
+                key: keys[3].to_vec(),
+                value: values[3].to_vec(),
+                index: 3,
+                proof: vec![leaves[2], node1],
+                indices: vec![true, true],
+            },
+        ];
+
+        (root, neighbours)
+    }
+
+    #[test]
+    fn test_non_membership_between_neighbors() {
+        let (root, n) = sorted_tree();
+
+        // "d" sits between "c" (index 1) and "e" (index 2).
+        assert!(verify_merkle_non_membership(
+            b"d",
+            root,
+            4,
+            Some(&n[1]),
+            Some(&n[2]),
+            ALG
+        ));
+
+        // A key that is actually present must not prove absent.
+        assert!(!verify_merkle_non_membership(
+            b"c",
+            root,
+            4,
+            Some(&n[1]),
+            Some(&n[2]),
+            ALG
+        ));
+
+        // Non-adjacent neighbours are rejected even though they straddle the key.
+        assert!(!verify_merkle_non_membership(
+            b"d",
+            root,
+            4,
+            Some(&n[0]),
+            Some(&n[2]),
+            ALG
+        ));
+
+        // A prover lies about `index` to forge adjacency: it proves "a"@0 and
+        // "e"@2 straddle present key "c"@1 but restates "e"'s index as 1 so the
+        // `right.index == left.index + 1` check passes. Binding `index` to the
+        // proof orientation rejects this — the restated index no longer matches
+        // the folded `indices`.
+        let mut liar = n[2].clone();
+        liar.index = 1;
+        assert!(!verify_merkle_non_membership(
+            b"c",
+            root,
+            4,
+            Some(&n[0]),
+            Some(&liar),
+            ALG
+        ));
+    }
+
+    #[test]
+    fn test_non_membership_boundaries() {
+        let (root, n) = sorted_tree();
+
+        // Below the smallest key: only a right neighbour at index 0.
+        assert!(verify_merkle_non_membership(b"0", root, 4, None, Some(&n[0]), ALG));
+
+        // Above the largest key: only a left neighbour, which must be the last leaf.
+        assert!(verify_merkle_non_membership(b"z", root, 4, Some(&n[3]), None, ALG));
+
+        // A prover tries to prove present key "e"@2 absent by offering the
+        // smallest leaf "a"@0 as a left-only neighbour. Anchoring the left
+        // boundary to `leaf_count - 1` rejects it since 0 != 3.
+        assert!(!verify_merkle_non_membership(b"e", root, 4, Some(&n[0]), None, ALG));
+
+        // A right-only proof whose neighbour is not the smallest leaf fails.
+        assert!(!verify_merkle_non_membership(b"0", root, 4, None, Some(&n[1]), ALG));
+
+        // Nor can the prover forge the index-0 boundary: restating "c"@1 as
+        // index 0 is caught by the index/orientation binding.
+        let mut liar = n[1].clone();
+        liar.index = 0;
+        assert!(!verify_merkle_non_membership(b"0", root, 4, None, Some(&liar), ALG));
+
+        // At least one neighbour is required.
+        assert!(!verify_merkle_non_membership(b"d", root, 4, None, None, ALG));
+    }
+
+    #[test]
+    fn test_batch_insert_into_empty_slot() {
+        // A 4-leaf tree whose slot 0 is "empty" (value is the zero hash).
+        let keys: [&[u8]; 4] = [b"a", b"b", b"c", b"d"];
+        let empty = [0u8; 32];
+        let l0 = compute_kv_leaf_hash(keys[0], &empty, ALG);
+        let l1 = compute_kv_leaf_hash(keys[1], b"v1", ALG);
+        let l2 = compute_kv_leaf_hash(keys[2], b"v2", ALG);
+        let l3 = compute_kv_leaf_hash(keys[3], b"v3", ALG);
+
+        let node_a = hash_pair(l0, l1, ALG);
+        let node_b = hash_pair(l2, l3, ALG);
+        let old_root = hash_pair(node_a, node_b, ALG);
+
+        // Insert a real value into the empty slot 0.
+        let update = StateUpdate {
+            key: keys[0].to_vec(),
+            leaf_index: 0,
+            old_value: empty.to_vec(),
+            new_value: b"v0".to_vec(),
+            sibling_path: vec![l1, node_b],
+            indices: vec![false, false],
+        };
+
+        let new_l0 = compute_kv_leaf_hash(keys[0], b"v0", ALG);
+        let expected_root = hash_pair(hash_pair(new_l0, l1, ALG), node_b, ALG);
+
+        let new_root = apply_state_updates(old_root, &[update], ALG).expect("valid update");
+        assert_eq!(new_root, expected_root);
+    }
+
+    #[test]
+    fn test_batch_sequential_sibling_updates() {
+        // Two sequential writes to sibling leaves, where the second update's
+        // sibling path must reference the *already updated* first leaf.
+        let keys: [&[u8]; 4] = [b"a", b"b", b"c", b"d"];
+        let l0 = compute_kv_leaf_hash(keys[0], b"v0", ALG);
+        let l1 = compute_kv_leaf_hash(keys[1], b"v1", ALG);
+        let l2 = compute_kv_leaf_hash(keys[2], b"v2", ALG);
+        let l3 = compute_kv_leaf_hash(keys[3], b"v3", ALG);
+
+        let node_b = hash_pair(l2, l3, ALG);
+        let old_root = hash_pair(hash_pair(l0, l1, ALG), node_b, ALG);
+
+        let new_l0 = compute_kv_leaf_hash(keys[0], b"w0", ALG);
+        let new_l1 = compute_kv_leaf_hash(keys[1], b"w1", ALG);
+
+        let updates = vec![
+            StateUpdate {
+                key: keys[0].to_vec(),
+                leaf_index: 0,
+                old_value: b"v0".to_vec(),
+                new_value: b"w0".to_vec(),
+                sibling_path: vec![l1, node_b],
+                indices: vec![false, false],
+            },
+            StateUpdate {
+                key: keys[1].to_vec(),
+                leaf_index: 1,
+                old_value: b"v1".to_vec(),
+                new_value: b"w1".to_vec(),
+                // Sibling is the freshly updated leaf 0, proving the root was fed forward.
+                sibling_path: vec![new_l0, node_b],
+                indices: vec![true, false],
+            },
+        ];
+
+        let expected_root = hash_pair(hash_pair(new_l0, new_l1, ALG), node_b, ALG);
+        let new_root = apply_state_updates(old_root, &updates, ALG).expect("valid batch");
+        assert_eq!(new_root, expected_root);
+
+        // A stale sibling for the second update (old leaf 0) must be rejected.
+        let mut bad = updates.clone();
+        bad[1].sibling_path = vec![l0, node_b];
+        assert!(apply_state_updates(old_root, &bad, ALG).is_none());
+    }
+
+    #[test]
+    fn test_multiproof_two_leaves() {
+        // 4-leaf tree.
+        let leaf1 = compute_leaf_hash(b"data1", ALG);
+        let leaf2 = compute_leaf_hash(b"data2", ALG);
+        let leaf3 = compute_leaf_hash(b"data3", ALG);
+        let leaf4 = compute_leaf_hash(b"data4", ALG);
+        let node1 = hash_pair(leaf1, leaf2, ALG);
+        let node2 = hash_pair(leaf3, leaf4, ALG);
+        let root = hash_pair(node1, node2, ALG);
+
+        // Prove leaves 0 and 2: neither's sibling is proven, so the proof carries
+        // leaf2 (sibling of 0) then leaf4 (sibling of 2), in index order.
+        let leaves = [(0u32, leaf1), (2u32, leaf3)];
+        let proof = [leaf2, leaf4];
+        assert!(verify_merkle_multiproof(&leaves, &proof, root, ALG));
+
+        // Prove siblings 0 and 1: they pair directly, so the proof is just node2.
+        let leaves = [(0u32, leaf1), (1u32, leaf2)];
+        let proof = [node2];
+        assert!(verify_merkle_multiproof(&leaves, &proof, root, ALG));
+
+        // A wrong sibling must fail.
+        assert!(!verify_merkle_multiproof(&leaves, &[node1], root, ALG));
+    }
+
+    #[test]
+    fn test_multiproof_three_leaves() {
+        let leaf1 = compute_leaf_hash(b"data1", ALG);
+        let leaf2 = compute_leaf_hash(b"data2", ALG);
+        let leaf3 = compute_leaf_hash(b"data3", ALG);
+        let leaf4 = compute_leaf_hash(b"data4", ALG);
+        let node1 = hash_pair(leaf1, leaf2, ALG);
+        let node2 = hash_pair(leaf3, leaf4, ALG);
+        let root = hash_pair(node1, node2, ALG);
+
+        // Prove leaves 0, 1 and 3. Leaves 0 and 1 pair directly at the bottom;
+        // leaf 3 pulls leaf4 (index 2) from the proof. At the next level node1 is
+        // recomputed and pairs with the recomputed node2 — no further proof needed.
+        let leaves = [(0u32, leaf1), (1u32, leaf2), (3u32, leaf4)];
+        let proof = [leaf3];
+        assert!(verify_merkle_multiproof(&leaves, &proof, root, ALG));
+
+        // Passing the leaves out of order still verifies (they are sorted internally).
+        let leaves = [(3u32, leaf4), (0u32, leaf1), (1u32, leaf2)];
+        assert!(verify_merkle_multiproof(&leaves, &proof, root, ALG));
     }
 }
@@ -8,39 +8,155 @@
 sp1_zkvm::entrypoint!(main);
 
 use alloy_sol_types::SolType;
-use merkle_tree_lib::{verify_merkle_path, PublicValuesStruct};
+use merkle_tree_lib::{
+    apply_state_updates, compute_leaf_hash, verify_merkle_multiproof, verify_merkle_non_membership,
+    verify_merkle_path, verify_merkle_proof_indexed, HashAlgorithm, NeighborProof,
+    PublicValuesStruct, StateUpdate,
+};
+
+/// Prove that a key is absent from a key-sorted tree committed to by `root`.
+/// Any other mode byte (notably `0`) runs the original single-leaf membership check.
+const MODE_NON_MEMBERSHIP: u8 = 1;
+/// Prove that a batch of updates transforms `old_root` into `new_root`.
+const MODE_BATCH_UPDATE: u8 = 2;
+/// Prove many leaves against one `root` from a single compressed multiproof.
+const MODE_MULTIPROOF: u8 = 3;
+/// Prove that an erasure-coded data-availability shard belongs to `root`.
+const MODE_DA_SHARD: u8 = 4;
 
 pub fn main() {
-    // Read the leaf hash (32 bytes)
-    let leaf: [u8; 32] = sp1_zkvm::io::read::<[u8; 32]>();
+    // Read the verification mode.
+    let mode: u8 = sp1_zkvm::io::read::<u8>();
+
+    // Read the hash algorithm selector and decode it.
+    let hash_algorithm: u8 = sp1_zkvm::io::read::<u8>();
+    let alg = HashAlgorithm::from_byte(hash_algorithm);
 
-    // Read the expected root hash (32 bytes)
+    // Read the expected root hash (32 bytes), shared by every mode.
     let root: [u8; 32] = sp1_zkvm::io::read::<[u8; 32]>();
 
-    // Read the proof length
-    let proof_len: u32 = sp1_zkvm::io::read::<u32>();
+    // Public values default to zero/false and are filled in per mode below.
+    let mut leaf = [0u8; 32];
+    let mut key = [0u8; 32];
+    let mut old_root = [0u8; 32];
+    let mut new_root = [0u8; 32];
+    let mut leaf_count: u32 = 0;
+    let mut index: u64 = 0;
+    let mut depth: u32 = 0;
+    let mut shard_index: u32 = 0;
+    let mut shard_hash = [0u8; 32];
+    let mut is_member = false;
+    let is_valid;
 
-    // Read the proof (array of sibling hashes)
-    let mut proof = Vec::new();
-    for _ in 0..proof_len {
-        let sibling: [u8; 32] = sp1_zkvm::io::read::<[u8; 32]>();
-        proof.push(sibling);
-    }
+    if mode == MODE_BATCH_UPDATE {
+        // `root` doubles as the starting `old_root` for the batch.
+        old_root = root;
 
-    // Read the indices (boolean array indicating left/right positions)
-    let mut indices = Vec::new();
-    for _ in 0..proof_len {
-        let index: bool = sp1_zkvm::io::read::<bool>();
-        indices.push(index);
-    }
+        // Read the claimed resulting root.
+        new_root = sp1_zkvm::io::read::<[u8; 32]>();
+
+        // Read the ordered batch of updates.
+        let num_updates: u32 = sp1_zkvm::io::read::<u32>();
+        let mut updates = Vec::new();
+        for _ in 0..num_updates {
+            updates.push(sp1_zkvm::io::read::<StateUpdate>());
+        }
+
+        // The batch is valid iff it folds `old_root` into exactly `new_root`.
+        is_valid = apply_state_updates(old_root, &updates, alg) == Some(new_root);
+    } else if mode == MODE_MULTIPROOF {
+        // Read the proven leaves as (index, hash) pairs.
+        let num_leaves: u32 = sp1_zkvm::io::read::<u32>();
+        let mut leaves = Vec::new();
+        for _ in 0..num_leaves {
+            let index: u32 = sp1_zkvm::io::read::<u32>();
+            let hash: [u8; 32] = sp1_zkvm::io::read::<[u8; 32]>();
+            leaves.push((index, hash));
+        }
+        leaf_count = num_leaves;
+
+        // Read the shared sibling hashes, in index order.
+        let proof_len: u32 = sp1_zkvm::io::read::<u32>();
+        let mut proof = Vec::new();
+        for _ in 0..proof_len {
+            proof.push(sp1_zkvm::io::read::<[u8; 32]>());
+        }
+
+        is_valid = verify_merkle_multiproof(&leaves, &proof, root, alg);
+    } else if mode == MODE_DA_SHARD {
+        // Read the shard index and the shard bytes.
+        shard_index = sp1_zkvm::io::read::<u32>();
+        let shard: Vec<u8> = sp1_zkvm::io::read::<Vec<u8>>();
+        shard_hash = compute_leaf_hash(&shard, alg);
+
+        // Read the shard's membership proof against the data-availability root.
+        let proof_len: u32 = sp1_zkvm::io::read::<u32>();
+        let mut proof = Vec::new();
+        for _ in 0..proof_len {
+            proof.push(sp1_zkvm::io::read::<[u8; 32]>());
+        }
+        let mut indices = Vec::new();
+        for _ in 0..proof_len {
+            indices.push(sp1_zkvm::io::read::<bool>());
+        }
 
-    // Verify the Merkle path
-    let is_valid = verify_merkle_path(leaf, root, &proof, &indices);
+        is_valid = verify_merkle_path(shard_hash, root, &proof, &indices, alg);
+    } else if mode == MODE_NON_MEMBERSHIP {
+        // Read the queried key.
+        let queried: Vec<u8> = sp1_zkvm::io::read::<Vec<u8>>();
+
+        // Read the total leaf count, which anchors a left-only boundary proof to
+        // the final leaf (`leaf_count - 1`) and is committed below.
+        leaf_count = sp1_zkvm::io::read::<u32>();
+
+        // Read the optional left/right neighbour membership proofs.
+        let has_left: bool = sp1_zkvm::io::read::<bool>();
+        let left: Option<NeighborProof> = has_left.then(|| sp1_zkvm::io::read::<NeighborProof>());
+
+        let has_right: bool = sp1_zkvm::io::read::<bool>();
+        let right: Option<NeighborProof> = has_right.then(|| sp1_zkvm::io::read::<NeighborProof>());
+
+        is_valid =
+            verify_merkle_non_membership(&queried, root, leaf_count, left.as_ref(), right.as_ref(), alg);
+
+        // There is no single leaf for a non-membership proof; commit the queried
+        // key (left-aligned, zero-padded) so the fixture can test exclusion.
+        let take = queried.len().min(32);
+        key[..take].copy_from_slice(&queried[..take]);
+    } else {
+        // Read the leaf hash (32 bytes).
+        leaf = sp1_zkvm::io::read::<[u8; 32]>();
+
+        // Read the leaf position and tree depth.
+        index = sp1_zkvm::io::read::<u64>();
+        depth = sp1_zkvm::io::read::<u32>();
+
+        // Read the sibling branch, one hash per level.
+        let mut branch = Vec::new();
+        for _ in 0..depth {
+            let sibling: [u8; 32] = sp1_zkvm::io::read::<[u8; 32]>();
+            branch.push(sibling);
+        }
+
+        // Verify the Merkle proof using the index/depth form.
+        is_valid = verify_merkle_proof_indexed(leaf, &branch, depth as usize, index, root, alg);
+        is_member = is_valid;
+    }
 
     // Encode the public values of the program
     let bytes = PublicValuesStruct::abi_encode(&PublicValuesStruct {
         leaf: leaf.into(),
         root: root.into(),
+        key: key.into(),
+        old_root: old_root.into(),
+        new_root: new_root.into(),
+        leaf_count,
+        hash_algorithm,
+        index,
+        depth,
+        shard_index,
+        shard_hash: shard_hash.into(),
+        is_member,
         is_valid,
     });
 
@@ -12,7 +12,7 @@
 
 use alloy_sol_types::SolType;
 use clap::{Parser, ValueEnum};
-use merkle_tree_lib::{compute_leaf_hash, hash_pair, PublicValuesStruct};
+use merkle_tree_lib::{compute_leaf_hash, hash_pair, HashAlgorithm, PublicValuesStruct};
 use serde::{Deserialize, Serialize};
 use sp1_sdk::{
     include_elf, HashableKey, ProverClient, SP1ProofWithPublicValues, SP1Stdin, SP1VerifyingKey,
@@ -30,6 +30,8 @@ struct EVMArgs {
     data: String,
     #[arg(long, value_enum, default_value = "groth16")]
     system: ProofSystem,
+    #[arg(long, value_enum, default_value = "keccak256")]
+    hash: HashArg,
 }
 
 /// Enum representing the available proof systems
@@ -39,12 +41,30 @@ enum ProofSystem {
     Groth16,
 }
 
+/// The hash algorithm selectable from the command line.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum HashArg {
+    Sha256,
+    Keccak256,
+}
+
+impl From<HashArg> for HashAlgorithm {
+    fn from(arg: HashArg) -> Self {
+        match arg {
+            HashArg::Sha256 => HashAlgorithm::Sha256,
+            HashArg::Keccak256 => HashAlgorithm::Keccak256,
+        }
+    }
+}
+
 /// A fixture that can be used to test the verification of SP1 zkVM proofs inside Solidity.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SP1MerkleTreeProofFixture {
     leaf: String,
     root: String,
+    key: String,
+    is_member: bool,
     is_valid: bool,
     vkey: String,
     public_values: String,
@@ -65,36 +85,40 @@ fn main() {
     let (pk, vk) = client.setup(MERKLE_TREE_ELF);
 
     // Create a simple Merkle tree for demonstration
-    let leaf1 = compute_leaf_hash(args.data.as_bytes());
-    let leaf2 = compute_leaf_hash(b"data2");
-    let leaf3 = compute_leaf_hash(b"data3");
-    let leaf4 = compute_leaf_hash(b"data4");
+    let alg: HashAlgorithm = args.hash.into();
 
-    let node1 = hash_pair(leaf1, leaf2);
-    let node2 = hash_pair(leaf3, leaf4);
-    let root = hash_pair(node1, node2);
+    let leaf1 = compute_leaf_hash(args.data.as_bytes(), alg);
+    let leaf2 = compute_leaf_hash(b"data2", alg);
+    let leaf3 = compute_leaf_hash(b"data3", alg);
+    let leaf4 = compute_leaf_hash(b"data4", alg);
 
-    // We'll verify the path for leaf1 (position 0: left-left)
-    let proof = vec![leaf2, node2]; // Sibling hashes needed for verification
-    let indices = vec![false, false]; // false = current goes left, true = current goes right
+    let node1 = hash_pair(leaf1, leaf2, alg);
+    let node2 = hash_pair(leaf3, leaf4, alg);
+    let root = hash_pair(node1, node2, alg);
+
+    // We'll verify the path for leaf1 at index 0 (left-left) in a depth-2 tree.
+    let branch = vec![leaf2, node2]; // Sibling hashes, one per level
+    let index: u64 = 0;
+    let depth: u32 = branch.len() as u32;
 
     // Setup the inputs for the zkVM program
     let mut stdin = SP1Stdin::new();
-    stdin.write(&leaf1);
+    stdin.write(&0u8); // mode 0 = single-leaf membership
+    stdin.write(&alg.as_byte());
     stdin.write(&root);
-    stdin.write(&(proof.len() as u32));
+    stdin.write(&leaf1);
+    stdin.write(&index);
+    stdin.write(&depth);
 
-    for sibling in &proof {
+    for sibling in &branch {
         stdin.write(sibling);
     }
 
-    for &index in &indices {
-        stdin.write(&index);
-    }
-
     println!("Data: {}", args.data);
+    println!("Hash algorithm: {:?}", args.hash);
     println!("Leaf: 0x{}", hex::encode(leaf1));
     println!("Root: 0x{}", hex::encode(root));
+    println!("Leaf index: {}, depth: {}", index, depth);
     println!("Proof System: {:?}", args.system);
 
     // Generate the proof based on the selected proof system.
@@ -118,6 +142,16 @@ fn create_proof_fixture(
     let PublicValuesStruct {
         leaf,
         root,
+        key,
+        old_root: _,
+        new_root: _,
+        leaf_count: _,
+        hash_algorithm: _,
+        index: _,
+        depth: _,
+        shard_index: _,
+        shard_hash: _,
+        is_member,
         is_valid,
     } = PublicValuesStruct::abi_decode(bytes).unwrap();
 
@@ -125,6 +159,8 @@ fn create_proof_fixture(
     let fixture = SP1MerkleTreeProofFixture {
         leaf: format!("0x{}", hex::encode(leaf.0)),
         root: format!("0x{}", hex::encode(root.0)),
+        key: format!("0x{}", hex::encode(key.0)),
+        is_member,
         is_valid,
         vkey: vk.bytes32().to_string(),
         public_values: format!("0x{}", hex::encode(bytes)),
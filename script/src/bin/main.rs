@@ -11,13 +11,31 @@
 //! ```
 
 use alloy_sol_types::SolType;
-use clap::Parser;
-use merkle_tree_lib::{compute_leaf_hash, hash_pair, verify_merkle_path, PublicValuesStruct};
+use clap::{Parser, ValueEnum};
+use merkle_tree_lib::{
+    compute_leaf_hash, hash_pair, verify_merkle_proof_indexed, HashAlgorithm, PublicValuesStruct,
+};
 use sp1_sdk::{include_elf, ProverClient, SP1Stdin};
 
 /// The ELF (executable and linkable format) file for the Succinct RISC-V zkVM.
 pub const MERKLE_TREE_ELF: &[u8] = include_elf!("merkle-tree-program");
 
+/// The hash algorithm selectable from the command line.
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+enum HashArg {
+    Sha256,
+    Keccak256,
+}
+
+impl From<HashArg> for HashAlgorithm {
+    fn from(arg: HashArg) -> Self {
+        match arg {
+            HashArg::Sha256 => HashAlgorithm::Sha256,
+            HashArg::Keccak256 => HashAlgorithm::Keccak256,
+        }
+    }
+}
+
 /// The arguments for the command.
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -30,6 +48,9 @@ struct Args {
 
     #[arg(long, default_value = "Hello, World!")]
     data: String,
+
+    #[arg(long, value_enum, default_value = "keccak256")]
+    hash: HashArg,
 }
 
 fn main() {
@@ -56,42 +77,45 @@ fn main() {
     //   /  \     /  \
     //  leaf1 leaf2 leaf3 leaf4
 
-    let leaf1 = compute_leaf_hash(args.data.as_bytes());
-    let leaf2 = compute_leaf_hash(b"data2");
-    let leaf3 = compute_leaf_hash(b"data3");
-    let leaf4 = compute_leaf_hash(b"data4");
+    let alg: HashAlgorithm = args.hash.into();
 
-    let node1 = hash_pair(leaf1, leaf2);
-    let node2 = hash_pair(leaf3, leaf4);
-    let root = hash_pair(node1, node2);
+    let leaf1 = compute_leaf_hash(args.data.as_bytes(), alg);
+    let leaf2 = compute_leaf_hash(b"data2", alg);
+    let leaf3 = compute_leaf_hash(b"data3", alg);
+    let leaf4 = compute_leaf_hash(b"data4", alg);
 
-    // We'll verify the path for leaf1 (position 0: left-left)
-    let proof = vec![leaf2, node2]; // Sibling hashes needed for verification
-    let indices = vec![false, false]; // false = current goes left, true = current goes right
+    let node1 = hash_pair(leaf1, leaf2, alg);
+    let node2 = hash_pair(leaf3, leaf4, alg);
+    let root = hash_pair(node1, node2, alg);
+
+    // We'll verify the path for leaf1 at index 0 (left-left) in a depth-2 tree.
+    let branch = vec![leaf2, node2]; // Sibling hashes, one per level
+    let index: u64 = 0;
+    let depth: u32 = branch.len() as u32;
 
     println!("Data: {}", args.data);
+    println!("Hash algorithm: {:?}", args.hash);
     println!("Leaf hash: {:?}", hex::encode(leaf1));
     println!("Root hash: {:?}", hex::encode(root));
-    println!("Proof length: {}", proof.len());
+    println!("Leaf index: {}, depth: {}", index, depth);
 
     // Verify the path locally first
-    let is_valid = verify_merkle_path(leaf1, root, &proof, &indices);
+    let is_valid = verify_merkle_proof_indexed(leaf1, &branch, depth as usize, index, root, alg);
     println!("Local verification result: {}", is_valid);
 
     // Setup the inputs for the zkVM program
     let mut stdin = SP1Stdin::new();
-    stdin.write(&leaf1);
+    stdin.write(&0u8); // mode 0 = single-leaf membership
+    stdin.write(&alg.as_byte());
     stdin.write(&root);
-    stdin.write(&(proof.len() as u32));
+    stdin.write(&leaf1);
+    stdin.write(&index);
+    stdin.write(&depth);
 
-    for sibling in &proof {
+    for sibling in &branch {
         stdin.write(sibling);
     }
 
-    for &index in &indices {
-        stdin.write(&index);
-    }
-
     if args.execute {
         // Execute the program
         let (output, report) = client.execute(MERKLE_TREE_ELF, &stdin).run().unwrap();
@@ -102,6 +126,16 @@ fn main() {
         let PublicValuesStruct {
             leaf,
             root: output_root,
+            key: _,
+            old_root: _,
+            new_root: _,
+            leaf_count: _,
+            hash_algorithm: _,
+            index: _,
+            depth: _,
+            shard_index: _,
+            shard_hash: _,
+            is_member: _,
             is_valid,
         } = decoded;
 